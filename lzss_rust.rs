@@ -1,124 +1,143 @@
-struct BitWriter {
-    buffer: Vec<u8>,
+use std::io::{self, Read, Write};
+
+struct BitWriter<W: Write> {
+    writer: W,
     byte: u8,
     bit_count: u8,
 }
 
-impl BitWriter {
-    fn new(capacity: usize) -> BitWriter {
+impl<W: Write> BitWriter<W> {
+    fn new(writer: W) -> BitWriter<W> {
         return BitWriter {
-            buffer: Vec::with_capacity(capacity),
+            writer,
             byte: 0,
             bit_count: 0,
         };
     }
 
-    fn flush(&mut self) {
+    fn flush(&mut self) -> io::Result<()> {
         if self.bit_count == 0 {
-            return;
+            return Ok(());
         }
 
         if self.bit_count < 8 {
             self.byte <<= 8 - self.bit_count;
         }
 
-        self.buffer.push(self.byte);
+        self.writer.write_all(&[self.byte])?;
         self.byte = 0;
         self.bit_count = 0;
+
+        return Ok(());
     }
 
-    fn write_bit(&mut self, bit: bool) {
+    fn write_bit(&mut self, bit: bool) -> io::Result<()> {
         self.byte <<= 1;
         self.byte |= if bit { 1 } else { 0 };
 
         self.bit_count += 1;
 
         if self.bit_count == 8 {
-            self.flush();
+            return self.flush();
         }
+
+        return Ok(());
     }
 
-    fn write_u32(&mut self, number: u32, bits: u8) {
+    fn write_u32(&mut self, number: u32, bits: u8) -> io::Result<()> {
         let mut bits = bits;
 
         while bits > 0 {
             let mask = 1 << (bits - 1);
             let bit = (number & mask) > 0;
 
-            self.write_bit(bit);
+            self.write_bit(bit)?;
 
             bits -= 1;
         }
+
+        return Ok(());
     }
 
-    fn write_7bit_u32(&mut self, number: u32) {
+    fn write_7bit_u32(&mut self, number: u32) -> io::Result<()> {
         let mut n = number;
 
-        while n > 127 {
-            let b = 128 | (n & 127);
+        loop {
+            let mut b = n & 127;
+            n >>= 7;
 
-            self.write_u32(b, 8);
+            if n > 0 {
+                b |= 128;
+            }
 
-            n >>= 7;
-        }
+            self.write_u32(b, 8)?;
 
-        if n > 0 {
-            self.write_u32(n & 127, 8);
+            if n == 0 {
+                break;
+            }
         }
+
+        return Ok(());
+    }
+
+    fn into_inner(self) -> W {
+        return self.writer;
     }
 }
 
-struct BitReader<'a> {
-    buffer: &'a [u8],
-    position: usize,
+struct BitReader<R: Read> {
+    reader: R,
     byte: u8,
     bit_count: u8,
 }
 
-impl<'a> BitReader<'a> {
-    fn new(buffer: &'a [u8]) -> BitReader {
+impl<R: Read> BitReader<R> {
+    fn new(reader: R) -> BitReader<R> {
         return BitReader {
-            buffer,
-            position: 0,
+            reader,
             byte: 0,
             bit_count: 0,
         };
     }
 
-    fn unflush(&mut self) {
-        self.byte = self.buffer[self.position];
-        self.position += 1;
+    fn unflush(&mut self) -> io::Result<()> {
+        let mut byte = [0u8; 1];
+        self.reader.read_exact(&mut byte)?;
+
+        self.byte = byte[0];
         self.bit_count = 8;
+
+        return Ok(());
     }
 
-    fn read_bit(&mut self) -> bool {
+    fn read_bit(&mut self) -> io::Result<bool> {
         if self.bit_count == 0 {
-            self.unflush();
+            self.unflush()?;
         }
 
         self.bit_count -= 1;
 
-        return (self.byte & (1 << self.bit_count)) > 0;
+        return Ok((self.byte & (1 << self.bit_count)) > 0);
     }
 
-    fn read_u32(&mut self, bits: u8) -> u32 {
+    fn read_u32(&mut self, bits: u8) -> io::Result<u32> {
         let mut value: u32 = 0;
 
         for _ in 0..bits {
             value <<= 1;
-            let bit = self.read_bit();
+            let bit = self.read_bit()?;
             value |= if bit { 1 } else { 0 };
         }
 
-        return value;
+        return Ok(value);
     }
 
-    fn read_7bit_u32(&mut self) -> u32 {
+    fn read_7bit_u32(&mut self) -> io::Result<u32> {
         let mut n: u32 = 0;
         let mut shift: u32 = 0;
 
         loop {
-            let byte = self.read_u32(8);
+            let byte = self.read_u32(8)?;
 
             n |= (byte & 127) << shift;
             shift += 7;
@@ -128,7 +147,7 @@ impl<'a> BitReader<'a> {
             }
         }
 
-        return n;
+        return Ok(n);
     }
 }
 
@@ -141,6 +160,10 @@ struct Lzss {
 
     minimum_length: u32,
     maximum_length: u32,
+
+    max_chain: u32,
+
+    lazy: bool,
 }
 
 fn lzss_get_upper_bound(input_length: usize) -> usize {
@@ -149,17 +172,65 @@ fn lzss_get_upper_bound(input_length: usize) -> usize {
     return (total_bits / 8) + if total_bits % 8 == 0 { 1 } else { 0 };
 }
 
-fn lzss_new(offset_bits: u8, length_bits: u8, minimum_length: u32) -> Lzss {
+fn lzss_new(
+    offset_bits: u8,
+    length_bits: u8,
+    minimum_length: u32,
+    max_chain: u32,
+    lazy: bool,
+) -> Lzss {
     return Lzss {
         offset_bits,
         length_bits,
         maximum_offset: (1 << offset_bits) - 1,
         minimum_length,
         maximum_length: (1 << length_bits) - 1,
+        max_chain,
+        lazy,
     };
 }
 
-fn __lzss_get_longest_match<'a>(lzss: Lzss, input: &'a [u8], index: u32) -> (u32, u32) {
+const HASH_BITS: u32 = 15;
+const HASH_SIZE: usize = 1 << HASH_BITS;
+
+struct MatchFinder {
+    head: Vec<i32>,
+    prev: Vec<i32>,
+}
+
+impl MatchFinder {
+    fn new(input_length: usize) -> MatchFinder {
+        return MatchFinder {
+            head: vec![-1; HASH_SIZE],
+            prev: vec![-1; input_length],
+        };
+    }
+
+    fn hash(input: &[u8], index: u32) -> usize {
+        let available = std::cmp::min(4, input.len() - index as usize);
+
+        let mut bytes = [0u8; 4];
+        bytes[..available].copy_from_slice(&input[index as usize..index as usize + available]);
+
+        let value = u32::from_le_bytes(bytes);
+
+        return (value.wrapping_mul(0x9E3779B1) >> (32 - HASH_BITS)) as usize;
+    }
+
+    fn insert(&mut self, input: &[u8], index: u32) {
+        let h = MatchFinder::hash(input, index);
+
+        self.prev[index as usize] = self.head[h];
+        self.head[h] = index as i32;
+    }
+}
+
+fn __lzss_get_longest_match<'a>(
+    lzss: Lzss,
+    input: &'a [u8],
+    index: u32,
+    finder: &MatchFinder,
+) -> (u32, u32) {
     let input_lenght = input.len() as u32;
 
     if index + lzss.minimum_length >= input_lenght {
@@ -168,28 +239,36 @@ fn __lzss_get_longest_match<'a>(lzss: Lzss, input: &'a [u8], index: u32) -> (u32
 
     let mut best_offset: u32 = 0;
     let mut best_length: u32 = 0;
-    let mut offset: u32 = if lzss.maximum_offset > index {
+
+    let minimum_offset = if lzss.maximum_offset > index {
         0
     } else {
         index - lzss.maximum_offset
     };
 
-    while offset < index && offset < input_lenght {
+    let h = MatchFinder::hash(input, index);
+    let mut candidate = finder.head[h];
+    let mut chain_steps: u32 = 0;
+
+    while candidate >= 0 && (candidate as u32) >= minimum_offset && chain_steps < lzss.max_chain {
+        let offset = candidate as u32;
         let mut length: u32 = 0;
 
-        while offset + length < input_lenght
+        while length < lzss.maximum_length
+            && offset + length < input_lenght
             && index + length < input_lenght
             && input[(offset + length) as usize] == input[(index + length) as usize]
         {
             length += 1;
         }
 
-        if length >= best_length {
+        if length > best_length {
             best_offset = offset;
             best_length = length;
         }
 
-        offset += 1;
+        candidate = finder.prev[offset as usize];
+        chain_steps += 1;
     }
 
     return (
@@ -198,62 +277,653 @@ fn __lzss_get_longest_match<'a>(lzss: Lzss, input: &'a [u8], index: u32) -> (u32
     );
 }
 
-fn lzss_encode<'a>(lzss: Lzss, input: &[u8]) -> Vec<u8> {
-    let upper_bound = lzss_get_upper_bound(input.len());
+fn lzss_encode_writer<W: Write>(lzss: Lzss, input: &[u8], writer: W) -> io::Result<W> {
+    let mut writer = BitWriter::new(writer);
 
-    let mut writer = BitWriter::new(upper_bound);
+    writer.write_7bit_u32(input.len() as u32)?;
 
-    writer.write_7bit_u32(input.len() as u32);
+    let mut finder = MatchFinder::new(input.len());
 
     let mut index: u32 = 0;
     while index < input.len() as u32 {
-        let _match = __lzss_get_longest_match(lzss, input, index);
+        let mut _match = __lzss_get_longest_match(lzss, input, index, &finder);
+        finder.insert(input, index);
+
+        if lzss.lazy && _match.1 >= lzss.minimum_length && index + 1 < input.len() as u32 {
+            let next_match = __lzss_get_longest_match(lzss, input, index + 1, &finder);
+
+            if next_match.1 > _match.1 {
+                _match = (0, 0);
+            }
+        }
 
         if _match.1 >= lzss.minimum_length {
-            writer.write_bit(true);
-            writer.write_u32(_match.0, lzss.offset_bits);
-            writer.write_u32(_match.1, lzss.length_bits);
-            index += _match.1;
+            writer.write_bit(true)?;
+            writer.write_u32(_match.0, lzss.offset_bits)?;
+            writer.write_u32(_match.1, lzss.length_bits)?;
+
+            let end = index + _match.1;
+            index += 1;
+            while index < end {
+                finder.insert(input, index);
+                index += 1;
+            }
         } else {
-            writer.write_bit(false);
-            writer.write_u32(input[index as usize] as u32, 8);
+            writer.write_bit(false)?;
+            writer.write_u32(input[index as usize] as u32, 8)?;
             index += 1;
         }
     }
 
-    writer.flush();
+    writer.flush()?;
 
-    return writer.buffer;
+    return Ok(writer.into_inner());
 }
 
-fn lzss_decode<'a>(lzss: Lzss, input: &[u8]) -> Vec<u8> {
-    let mut reader = BitReader::new(input);
+fn lzss_encode(lzss: Lzss, input: &[u8]) -> io::Result<Vec<u8>> {
+    let upper_bound = lzss_get_upper_bound(input.len());
 
-    let original_length = reader.read_7bit_u32() as usize;
+    return lzss_encode_writer(lzss, input, Vec::with_capacity(upper_bound));
+}
 
-    let mut output: Vec<u8> = vec![0; original_length];
+const HISTORY_WINDOW_MINIMUM_CAPACITY: usize = 1;
 
-    let mut index = 0;
-    while index < original_length {
-        let is_pair = reader.read_bit();
+struct HistoryWindow {
+    buffer: Vec<u8>,
+    capacity: usize,
+    total_written: u64,
+}
 
-        if is_pair {
-            let offset = reader.read_u32(lzss.offset_bits) as usize;
-            let length = reader.read_u32(lzss.length_bits) as usize;
+impl HistoryWindow {
+    fn new(capacity: usize) -> HistoryWindow {
+        let capacity = std::cmp::max(capacity, HISTORY_WINDOW_MINIMUM_CAPACITY);
+
+        return HistoryWindow {
+            buffer: vec![0; capacity],
+            capacity,
+            total_written: 0,
+        };
+    }
+
+    fn push(&mut self, byte: u8) {
+        let index = (self.total_written as usize) % self.capacity;
+
+        self.buffer[index] = byte;
+        self.total_written += 1;
+    }
+
+    fn get(&self, offset: usize) -> u8 {
+        let position = self.total_written as usize - offset;
+
+        return self.buffer[position % self.capacity];
+    }
+
+    // Falls back to `scratch` only when the run wraps past the end of the ring buffer.
+    fn copy_match<W: Write>(
+        &mut self,
+        offset: usize,
+        length: usize,
+        writer: &mut W,
+        scratch: &mut Vec<u8>,
+    ) -> io::Result<()> {
+        if offset == 0 || offset as u64 > self.total_written {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "match offset must be a positive distance into the decoded data",
+            ));
+        }
+
+        let write_start = (self.total_written as usize) % self.capacity;
+        let source_start = (self.total_written as usize - offset) % self.capacity;
+
+        let fits_without_wrap =
+            write_start + length <= self.capacity && source_start + length <= self.capacity;
+
+        if fits_without_wrap {
+            if offset >= length {
+                self.buffer
+                    .copy_within(source_start..source_start + length, write_start);
+            } else {
+                self.buffer
+                    .copy_within(source_start..source_start + offset, write_start);
+
+                let mut produced = offset;
+                while produced < length {
+                    let copy_length = std::cmp::min(produced, length - produced);
+                    self.buffer.copy_within(
+                        write_start..write_start + copy_length,
+                        write_start + produced,
+                    );
+                    produced += copy_length;
+                }
+            }
+
+            writer.write_all(&self.buffer[write_start..write_start + length])?;
+            self.total_written += length as u64;
+        } else {
+            scratch.clear();
+            scratch.reserve(length);
 
             for i in 0..length {
-                output[index + i] = output[(index - offset) + i];
+                let byte = if i < offset {
+                    self.get(offset - i)
+                } else {
+                    scratch[i - offset]
+                };
+                scratch.push(byte);
+            }
+
+            for &byte in scratch.iter() {
+                self.push(byte);
             }
 
-            index += length;
+            writer.write_all(scratch)?;
+        }
+
+        return Ok(());
+    }
+}
+
+fn lzss_decode_reader<R: Read, W: Write>(lzss: Lzss, reader: R, writer: W) -> io::Result<W> {
+    let mut reader = BitReader::new(reader);
+    let mut writer = writer;
+
+    let original_length = reader.read_7bit_u32()? as usize;
+
+    let mut window = HistoryWindow::new(lzss.maximum_offset as usize);
+    let mut scratch: Vec<u8> = Vec::with_capacity(lzss.maximum_length as usize);
+
+    let mut written = 0;
+    while written < original_length {
+        let is_pair = reader.read_bit()?;
+
+        if is_pair {
+            let offset = reader.read_u32(lzss.offset_bits)? as usize;
+            let length = reader.read_u32(lzss.length_bits)? as usize;
+
+            window.copy_match(offset, length, &mut writer, &mut scratch)?;
+
+            written += length;
         } else {
-            let literal = reader.read_u32(8) as u8;
-            output[index] = literal;
-            index += 1;
+            let literal = reader.read_u32(8)? as u8;
+            writer.write_all(&[literal])?;
+            window.push(literal);
+            written += 1;
+        }
+    }
+
+    return Ok(writer);
+}
+
+fn lzss_decode(lzss: Lzss, input: &[u8]) -> io::Result<Vec<u8>> {
+    return lzss_decode_reader(lzss, input, Vec::with_capacity(input.len()));
+}
+
+// Decodes a stream whose bytes arrive over multiple `feed` calls instead of all at once,
+// buffering only the not-yet-decodable tail of the compressed input and the sliding
+// `HistoryWindow` between calls, so arbitrarily large streams decode in bounded memory.
+struct LzssStreamDecoder {
+    lzss: Lzss,
+    window: HistoryWindow,
+    scratch: Vec<u8>,
+    pending: Vec<u8>,
+    byte_pos: usize,
+    bit_byte: u8,
+    bit_count: u8,
+    original_length: Option<usize>,
+    written: usize,
+}
+
+impl LzssStreamDecoder {
+    fn new(lzss: Lzss) -> LzssStreamDecoder {
+        return LzssStreamDecoder {
+            lzss,
+            window: HistoryWindow::new(lzss.maximum_offset as usize),
+            scratch: Vec::with_capacity(lzss.maximum_length as usize),
+            pending: Vec::new(),
+            byte_pos: 0,
+            bit_byte: 0,
+            bit_count: 0,
+            original_length: None,
+            written: 0,
+        };
+    }
+
+    fn is_finished(&self) -> bool {
+        return self.original_length.map_or(false, |length| self.written >= length);
+    }
+
+    fn checkpoint(&self) -> (usize, u8, u8) {
+        return (self.byte_pos, self.bit_byte, self.bit_count);
+    }
+
+    fn restore(&mut self, checkpoint: (usize, u8, u8)) {
+        self.byte_pos = checkpoint.0;
+        self.bit_byte = checkpoint.1;
+        self.bit_count = checkpoint.2;
+    }
+
+    fn read_bit(&mut self) -> io::Result<bool> {
+        if self.bit_count == 0 {
+            if self.byte_pos >= self.pending.len() {
+                return Err(io::Error::new(
+                    io::ErrorKind::UnexpectedEof,
+                    "not enough buffered input to decode the next token",
+                ));
+            }
+
+            self.bit_byte = self.pending[self.byte_pos];
+            self.byte_pos += 1;
+            self.bit_count = 8;
+        }
+
+        self.bit_count -= 1;
+
+        return Ok((self.bit_byte & (1 << self.bit_count)) > 0);
+    }
+
+    fn read_u32(&mut self, bits: u8) -> io::Result<u32> {
+        let mut value: u32 = 0;
+
+        for _ in 0..bits {
+            value <<= 1;
+            value |= if self.read_bit()? { 1 } else { 0 };
+        }
+
+        return Ok(value);
+    }
+
+    fn read_7bit_u32(&mut self) -> io::Result<u32> {
+        let mut n: u32 = 0;
+        let mut shift: u32 = 0;
+
+        loop {
+            let byte = self.read_u32(8)?;
+
+            n |= (byte & 127) << shift;
+            shift += 7;
+
+            if (byte & 128) == 0 || shift > 32 {
+                break;
+            }
+        }
+
+        return Ok(n);
+    }
+
+    // Feeds another chunk of compressed input, writing as much newly decoded output to
+    // `writer` as the buffered input makes available. Call again with more input (or an
+    // empty slice once the caller has no more) until `is_finished()` returns true.
+    fn feed<W: Write>(&mut self, chunk: &[u8], writer: &mut W) -> io::Result<()> {
+        self.pending.extend_from_slice(chunk);
+
+        loop {
+            if self.original_length.is_none() {
+                let checkpoint = self.checkpoint();
+
+                match self.read_7bit_u32() {
+                    Ok(value) => self.original_length = Some(value as usize),
+                    Err(_) => {
+                        self.restore(checkpoint);
+                        break;
+                    }
+                }
+            }
+
+            if self.written >= self.original_length.unwrap() {
+                break;
+            }
+
+            let checkpoint = self.checkpoint();
+
+            let is_pair = match self.read_bit() {
+                Ok(bit) => bit,
+                Err(_) => {
+                    self.restore(checkpoint);
+                    break;
+                }
+            };
+
+            if is_pair {
+                let offset_bits = self.lzss.offset_bits;
+                let length_bits = self.lzss.length_bits;
+
+                let (offset, length) = match (self.read_u32(offset_bits), self.read_u32(length_bits)) {
+                    (Ok(offset), Ok(length)) => (offset as usize, length as usize),
+                    _ => {
+                        self.restore(checkpoint);
+                        break;
+                    }
+                };
+
+                self.window.copy_match(offset, length, writer, &mut self.scratch)?;
+                self.written += length;
+            } else {
+                let literal = match self.read_u32(8) {
+                    Ok(value) => value as u8,
+                    Err(_) => {
+                        self.restore(checkpoint);
+                        break;
+                    }
+                };
+
+                writer.write_all(&[literal])?;
+                self.window.push(literal);
+                self.written += 1;
+            }
+        }
+
+        if self.byte_pos > 0 {
+            self.pending.drain(0..self.byte_pos);
+            self.byte_pos = 0;
         }
+
+        return Ok(());
+    }
+}
+
+const LZSS_FRAME_MAGIC: [u8; 4] = *b"LZS1";
+const LZSS_FRAME_VERSION: u8 = 1;
+const LZSS_FRAME_HEADER_LENGTH: usize = 4 + 1 + 1 + 1 + 4 + 4;
+
+fn crc32(data: &[u8]) -> u32 {
+    const POLYNOMIAL: u32 = 0xEDB88320;
+
+    let mut crc: u32 = 0xFFFFFFFF;
+
+    for &byte in data {
+        crc ^= byte as u32;
+
+        for _ in 0..8 {
+            let mask = (crc & 1).wrapping_neg();
+            crc = (crc >> 1) ^ (POLYNOMIAL & mask);
+        }
+    }
+
+    return !crc;
+}
+
+#[derive(Debug)]
+enum LzssFrameError {
+    TooShort,
+    BadMagic,
+    BadSync,
+    UnsupportedVersion(u8),
+    ChecksumMismatch { expected: u32, actual: u32 },
+    BlockIndexOutOfRange { index: usize, count: usize },
+    Corrupt(io::Error),
+}
+
+impl std::fmt::Display for LzssFrameError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            LzssFrameError::TooShort => write!(f, "frame is too short to contain a header"),
+            LzssFrameError::BadMagic => write!(f, "frame does not start with the lzss magic"),
+            LzssFrameError::BadSync => write!(f, "block does not start with the block sync marker"),
+            LzssFrameError::UnsupportedVersion(version) => {
+                write!(f, "unsupported frame version {}", version)
+            }
+            LzssFrameError::ChecksumMismatch { expected, actual } => write!(
+                f,
+                "crc32 mismatch: expected {:#010x}, got {:#010x}",
+                expected, actual
+            ),
+            LzssFrameError::BlockIndexOutOfRange { index, count } => write!(
+                f,
+                "block index {} is out of range (frame has {} blocks)",
+                index, count
+            ),
+            LzssFrameError::Corrupt(error) => write!(f, "frame payload is corrupt: {}", error),
+        }
+    }
+}
+
+impl std::error::Error for LzssFrameError {}
+
+impl From<io::Error> for LzssFrameError {
+    fn from(error: io::Error) -> LzssFrameError {
+        return LzssFrameError::Corrupt(error);
     }
+}
+
+fn lzss_encode_framed(lzss: Lzss, input: &[u8]) -> Vec<u8> {
+    let payload = lzss_encode(lzss, input).expect("writing to an in-memory buffer cannot fail");
+    let checksum = crc32(input);
+
+    let mut frame = Vec::with_capacity(LZSS_FRAME_HEADER_LENGTH + payload.len() + 4);
+
+    frame.extend_from_slice(&LZSS_FRAME_MAGIC);
+    frame.push(LZSS_FRAME_VERSION);
+    frame.push(lzss.offset_bits);
+    frame.push(lzss.length_bits);
+    frame.extend_from_slice(&lzss.minimum_length.to_le_bytes());
+    frame.extend_from_slice(&(input.len() as u32).to_le_bytes());
+    frame.extend_from_slice(&payload);
+    frame.extend_from_slice(&checksum.to_le_bytes());
 
-    return output;
+    return frame;
+}
+
+fn lzss_decode_framed(input: &[u8]) -> Result<Vec<u8>, LzssFrameError> {
+    if input.len() < LZSS_FRAME_HEADER_LENGTH + 4 {
+        return Err(LzssFrameError::TooShort);
+    }
+
+    if input[0..4] != LZSS_FRAME_MAGIC {
+        return Err(LzssFrameError::BadMagic);
+    }
+
+    let version = input[4];
+    if version != LZSS_FRAME_VERSION {
+        return Err(LzssFrameError::UnsupportedVersion(version));
+    }
+
+    let offset_bits = input[5];
+    let length_bits = input[6];
+    let minimum_length = u32::from_le_bytes(input[7..11].try_into().unwrap());
+
+    let lzss = lzss_new(offset_bits, length_bits, minimum_length, 0, false);
+
+    let payload_end = input.len() - 4;
+    let payload = &input[LZSS_FRAME_HEADER_LENGTH..payload_end];
+    let stored_checksum = u32::from_le_bytes(input[payload_end..].try_into().unwrap());
+
+    let output = lzss_decode(lzss, payload)?;
+
+    let actual_checksum = crc32(&output);
+    if actual_checksum != stored_checksum {
+        return Err(LzssFrameError::ChecksumMismatch {
+            expected: stored_checksum,
+            actual: actual_checksum,
+        });
+    }
+
+    return Ok(output);
+}
+
+const LZSS_BLOCK_FRAME_MAGIC: [u8; 4] = *b"LZB1";
+const LZSS_BLOCK_FRAME_VERSION: u8 = 1;
+const LZSS_BLOCK_FRAME_HEADER_LENGTH: usize = 4 + 1 + 1 + 1 + 4 + 4 + 4 + 4;
+const LZSS_BLOCK_SYNC: u32 = 0xAA55AA55;
+const LZSS_BLOCK_RECORD_HEADER_LENGTH: usize = 4 + 1 + 4 + 4;
+
+fn lzss_encode_block(lzss: Lzss, block: &[u8]) -> Vec<u8> {
+    let compressed = lzss_encode(lzss, block).expect("writing to an in-memory buffer cannot fail");
+
+    let stored = compressed.len() >= block.len();
+    let payload: &[u8] = if stored { block } else { &compressed };
+
+    let mut record = Vec::with_capacity(LZSS_BLOCK_RECORD_HEADER_LENGTH + payload.len());
+
+    record.extend_from_slice(&LZSS_BLOCK_SYNC.to_le_bytes());
+    record.push(stored as u8);
+    record.extend_from_slice(&(block.len() as u32).to_le_bytes());
+    record.extend_from_slice(&(payload.len() as u32).to_le_bytes());
+    record.extend_from_slice(payload);
+
+    return record;
+}
+
+// Splits `input` into independent `block_size`-byte blocks (each with its own reset match
+// history) and compresses them on a thread per block, so callers on a multi-core machine pay
+// roughly one block's worth of latency instead of the whole input's.
+fn lzss_encode_blocked(lzss: Lzss, input: &[u8], block_size: usize) -> Vec<u8> {
+    let block_size = std::cmp::max(block_size, 1);
+    let blocks: Vec<&[u8]> = input.chunks(block_size).collect();
+
+    let worker_count = std::thread::available_parallelism()
+        .map(|count| count.get())
+        .unwrap_or(1);
+
+    let mut encoded_blocks: Vec<Vec<u8>> = Vec::with_capacity(blocks.len());
+    for batch in blocks.chunks(worker_count) {
+        std::thread::scope(|scope| {
+            let handles: Vec<_> = batch
+                .iter()
+                .map(|&block| scope.spawn(move || lzss_encode_block(lzss, block)))
+                .collect();
+
+            for handle in handles {
+                encoded_blocks.push(handle.join().expect("block encoder thread panicked"));
+            }
+        });
+    }
+
+    let mut frame = Vec::with_capacity(LZSS_BLOCK_FRAME_HEADER_LENGTH);
+
+    frame.extend_from_slice(&LZSS_BLOCK_FRAME_MAGIC);
+    frame.push(LZSS_BLOCK_FRAME_VERSION);
+    frame.push(lzss.offset_bits);
+    frame.push(lzss.length_bits);
+    frame.extend_from_slice(&lzss.minimum_length.to_le_bytes());
+    frame.extend_from_slice(&(block_size as u32).to_le_bytes());
+    frame.extend_from_slice(&(input.len() as u32).to_le_bytes());
+    frame.extend_from_slice(&(blocks.len() as u32).to_le_bytes());
+
+    for encoded in &encoded_blocks {
+        frame.extend_from_slice(encoded);
+    }
+
+    return frame;
+}
+
+fn lzss_read_block_frame_header(input: &[u8]) -> Result<(Lzss, usize, usize), LzssFrameError> {
+    if input.len() < LZSS_BLOCK_FRAME_HEADER_LENGTH {
+        return Err(LzssFrameError::TooShort);
+    }
+
+    if input[0..4] != LZSS_BLOCK_FRAME_MAGIC {
+        return Err(LzssFrameError::BadMagic);
+    }
+
+    let version = input[4];
+    if version != LZSS_BLOCK_FRAME_VERSION {
+        return Err(LzssFrameError::UnsupportedVersion(version));
+    }
+
+    let offset_bits = input[5];
+    let length_bits = input[6];
+    let minimum_length = u32::from_le_bytes(input[7..11].try_into().unwrap());
+    let original_length = u32::from_le_bytes(input[15..19].try_into().unwrap()) as usize;
+    let block_count = u32::from_le_bytes(input[19..23].try_into().unwrap()) as usize;
+
+    let lzss = lzss_new(offset_bits, length_bits, minimum_length, 0, false);
+
+    return Ok((lzss, original_length, block_count));
+}
+
+struct LzssBlockRecordHeader {
+    stored: bool,
+    payload_start: usize,
+    payload_length: usize,
+    record_length: usize,
+}
+
+fn lzss_read_block_record_header(
+    input: &[u8],
+    offset: usize,
+) -> Result<LzssBlockRecordHeader, LzssFrameError> {
+    if offset + LZSS_BLOCK_RECORD_HEADER_LENGTH > input.len() {
+        return Err(LzssFrameError::TooShort);
+    }
+
+    let sync = u32::from_le_bytes(input[offset..offset + 4].try_into().unwrap());
+    if sync != LZSS_BLOCK_SYNC {
+        return Err(LzssFrameError::BadSync);
+    }
+
+    let stored = input[offset + 4] != 0;
+    let payload_length =
+        u32::from_le_bytes(input[offset + 9..offset + 13].try_into().unwrap()) as usize;
+    let payload_start = offset + LZSS_BLOCK_RECORD_HEADER_LENGTH;
+
+    if payload_start + payload_length > input.len() {
+        return Err(LzssFrameError::TooShort);
+    }
+
+    return Ok(LzssBlockRecordHeader {
+        stored,
+        payload_start,
+        payload_length,
+        record_length: LZSS_BLOCK_RECORD_HEADER_LENGTH + payload_length,
+    });
+}
+
+fn lzss_decode_block_payload(
+    lzss: Lzss,
+    input: &[u8],
+    header: &LzssBlockRecordHeader,
+) -> Result<Vec<u8>, LzssFrameError> {
+    let payload = &input[header.payload_start..header.payload_start + header.payload_length];
+
+    if header.stored {
+        return Ok(payload.to_vec());
+    }
+
+    return Ok(lzss_decode(lzss, payload)?);
+}
+
+fn lzss_decode_blocked(input: &[u8]) -> Result<Vec<u8>, LzssFrameError> {
+    let (lzss, original_length, block_count) = lzss_read_block_frame_header(input)?;
+
+    let mut output = Vec::with_capacity(original_length);
+    let mut offset = LZSS_BLOCK_FRAME_HEADER_LENGTH;
+
+    for _ in 0..block_count {
+        let header = lzss_read_block_record_header(input, offset)?;
+
+        output.extend_from_slice(&lzss_decode_block_payload(lzss, input, &header)?);
+        offset += header.record_length;
+    }
+
+    return Ok(output);
+}
+
+// Skips straight to `block_index` using each record's own size, without decompressing any of
+// the blocks before it.
+fn lzss_decode_blocked_at(input: &[u8], block_index: usize) -> Result<Vec<u8>, LzssFrameError> {
+    let (lzss, _original_length, block_count) = lzss_read_block_frame_header(input)?;
+
+    if block_index >= block_count {
+        return Err(LzssFrameError::BlockIndexOutOfRange {
+            index: block_index,
+            count: block_count,
+        });
+    }
+
+    let mut offset = LZSS_BLOCK_FRAME_HEADER_LENGTH;
+
+    for i in 0..block_count {
+        let header = lzss_read_block_record_header(input, offset)?;
+
+        if i == block_index {
+            return lzss_decode_block_payload(lzss, input, &header);
+        }
+
+        offset += header.record_length;
+    }
+
+    unreachable!();
 }
 
 fn main() {
@@ -266,14 +936,58 @@ fn main() {
 
     let file = std::fs::read(&args[1]).expect("Could not read file");
 
-    let lzss = lzss_new(10, 6, 2);
+    let lzss = lzss_new(10, 6, 2, 64, true);
 
-    let compressed = lzss_encode(lzss, file.as_slice());
+    let compressed = lzss_encode_framed(lzss, file.as_slice());
 
-    let uncompressed = lzss_decode(lzss, compressed.as_slice());
+    let uncompressed = match lzss_decode_framed(compressed.as_slice()) {
+        Ok(data) => data,
+        Err(error) => {
+            println!("Decode failed: {}", error);
+            std::process::exit(1);
+        }
+    };
 
     if !file.iter().zip(uncompressed.iter()).all(|(a, b)| a == b) {
         println!("Compression failed!");
         std::process::exit(1);
     }
+
+    let blocked = lzss_encode_blocked(lzss, file.as_slice(), 64 * 1024);
+
+    let unblocked = match lzss_decode_blocked(blocked.as_slice()) {
+        Ok(data) => data,
+        Err(error) => {
+            println!("Block decode failed: {}", error);
+            std::process::exit(1);
+        }
+    };
+
+    if !file.iter().zip(unblocked.iter()).all(|(a, b)| a == b) {
+        println!("Block compression failed!");
+        std::process::exit(1);
+    }
+
+    if !file.is_empty() {
+        if let Err(error) = lzss_decode_blocked_at(blocked.as_slice(), 0) {
+            println!("Block seek failed: {}", error);
+            std::process::exit(1);
+        }
+    }
+
+    let raw = lzss_encode(lzss, file.as_slice()).expect("writing to an in-memory buffer cannot fail");
+
+    let mut streamed = Vec::new();
+    let mut decoder = LzssStreamDecoder::new(lzss);
+    for chunk in raw.chunks(7) {
+        if let Err(error) = decoder.feed(chunk, &mut streamed) {
+            println!("Streamed decode failed: {}", error);
+            std::process::exit(1);
+        }
+    }
+
+    if !decoder.is_finished() || streamed != file {
+        println!("Streamed compression failed!");
+        std::process::exit(1);
+    }
 }